@@ -1,4 +1,9 @@
-//! A Rustc plugin that prints out the name of all items in a crate via StableMIR.
+//! A Rustc plugin that inspects a crate's items via StableMIR: list them as
+//! text or JSON (`--format json`), look one up by kind and path (`--find`),
+//! dump per-function MIR control-flow graphs (`--dump-mir`/`--format dot`),
+//! build an inter-procedural call graph (`--call-graph`), and optionally
+//! add upstream crates' functions and traits to the item listing
+//! (`--include-deps`; `--find` and `--dump-mir` only look at local items).
 
 #![feature(rustc_private)]
 
@@ -11,11 +16,14 @@ extern crate rustc_span;
 extern crate stable_mir;
 
 use clap::Parser;
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{DefKind, TyCtxt};
 use rustc_plugin::{CrateFilter, RustcPlugin, RustcPluginArgs, Utf8Path};
 use rustc_smir::rustc_internal;
 use serde::{Deserialize, Serialize};
-use std::{borrow::Cow, env, process::Command};
+use stable_mir::mir::mono::Instance;
+use stable_mir::mir::{Body, Operand, TerminatorKind, UnwindAction};
+use stable_mir::ty::{RigidTy, TyKind};
+use std::{borrow::Cow, collections::HashSet, env, fmt::Write as _, process::Command};
 
 // This struct is the plugin provided to the rustc_plugin framework,
 // and it must be exported for use by the CLI/driver binaries.
@@ -25,10 +33,60 @@ pub struct StablePlugin;
 // detail is up to you.
 #[derive(Parser, Serialize, Deserialize)]
 pub struct StablePluginArgs {
+    // When set, walk every function item's MIR and print a Graphviz DOT
+    // control-flow graph instead of the default item listing.
+    #[clap(long)]
+    dump_mir: bool,
+
+    /// Output format for the default item listing.
+    #[clap(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+
+    /// Locate a single item by `KIND:PATH` (e.g. `Fn:foo::bar`) instead of
+    /// dumping every item. May be repeated; fails the run if any query has
+    /// no match.
+    #[clap(long = "find")]
+    find: Vec<String>,
+
+    /// Build and print an inter-procedural call graph instead of the default
+    /// item listing, starting from `stable_mir::entry_fn()` (or every local
+    /// item when there is no entry point).
+    #[clap(long)]
+    call_graph: bool,
+
+    /// Maximum call-graph recursion depth, including calls that cross into
+    /// dependency crates.
+    #[clap(long, default_value_t = 8)]
+    call_graph_depth: usize,
+
+    /// Also report items from upstream crates, not just the local crate.
+    #[clap(long)]
+    include_deps: bool,
+
     #[clap(last = true)]
     cargo_args: Vec<String>,
 }
 
+// The rendering used by `print_all_items` and `run_call_graph`.
+#[derive(Clone, Copy, clap::ValueEnum, Serialize, Deserialize)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Dot,
+}
+
+// One item's entry in the `--format json` output.
+#[derive(Serialize)]
+struct ItemRecord {
+    def_kind: String,
+    path: String,
+    ty: String,
+    block_count: Option<usize>,
+    statement_count: Option<usize>,
+    // `None` for items in the local crate, `Some(name)` for `--include-deps` items.
+    crate_name: Option<String>,
+}
+
 impl RustcPlugin for StablePlugin {
     type Args = StablePluginArgs;
 
@@ -110,7 +168,17 @@ impl rustc_driver::Callbacks for StablePluginCallbacks {
         queries.global_ctxt().unwrap().enter(|tcx| {
             // We instantiate StableMIR and pass the callback into it.
             rustc_internal::run(tcx, || {
-                self.result = Some(print_all_items(tcx, &self.args));
+                self.result = Some(if !self.args.find.is_empty() {
+                    run_find(tcx, &self.args)
+                } else if self.args.call_graph {
+                    run_call_graph(tcx, &self.args)
+                } else if self.args.dump_mir || matches!(self.args.format, OutputFormat::Dot) {
+                    // `--dump-mir` and `--format dot` are two ways to ask for the
+                    // same CFG dump.
+                    dump_mir_cfgs(tcx, &self.args)
+                } else {
+                    print_all_items(tcx, &self.args)
+                });
             })
             .unwrap();
             // Check the callback return value.
@@ -128,10 +196,577 @@ impl rustc_driver::Callbacks for StablePluginCallbacks {
 }
 
 // Analysis callback.
-fn print_all_items(_tcx: TyCtxt, _args: &StablePluginArgs) -> rustc_driver::Compilation {
+fn print_all_items(tcx: TyCtxt, args: &StablePluginArgs) -> rustc_driver::Compilation {
+    match args.format {
+        // `--format dot` alone is routed to `dump_mir_cfgs` before this function
+        // is ever called (see the dispatch in `after_analysis`); this arm only
+        // exists so the match stays exhaustive.
+        OutputFormat::Text | OutputFormat::Dot => {
+            for item in stable_mir::all_local_items() {
+                let msg = format!("There is an item \"{:?}\" of type \"{}\"", item, item.ty());
+                println!("{msg}");
+            }
+            if args.include_deps {
+                for (crate_name, fn_def) in external_fn_defs() {
+                    println!(
+                        "There is an external item \"{:?}\" of type \"{}\" from crate \"{}\"",
+                        fn_def,
+                        fn_def.ty(),
+                        crate_name
+                    );
+                }
+                for (crate_name, trait_decl) in external_trait_decls() {
+                    println!(
+                        "There is an external item \"{:?}\" from crate \"{}\"",
+                        trait_decl, crate_name
+                    );
+                }
+            }
+        }
+        OutputFormat::Json => {
+            let mut records: Vec<ItemRecord> = stable_mir::all_local_items()
+                .into_iter()
+                .map(|item| item_record(tcx, &item))
+                .collect();
+            if args.include_deps {
+                records.extend(
+                    external_fn_defs()
+                        .into_iter()
+                        .map(|(crate_name, fn_def)| external_fn_record(tcx, crate_name, fn_def)),
+                );
+                records.extend(
+                    external_trait_decls()
+                        .into_iter()
+                        .map(|(crate_name, trait_decl)| external_trait_record(crate_name, trait_decl)),
+                );
+            }
+            println!("{}", serde_json::to_string_pretty(&records).unwrap());
+        }
+    }
+    rustc_driver::Compilation::Continue
+}
+
+// Builds the JSON-serializable record for a single local item, including MIR
+// body statistics when the item has a body.
+fn item_record(tcx: TyCtxt, item: &stable_mir::CrateItem) -> ItemRecord {
+    let (block_count, statement_count) = if item.has_body() {
+        let body = item.body();
+        let statement_count = body.blocks.iter().map(|bb| bb.statements.len()).sum();
+        (Some(body.blocks.len()), Some(statement_count))
+    } else {
+        (None, None)
+    };
+    ItemRecord {
+        def_kind: format!("{:?}", tcx.def_kind(rustc_internal::internal(tcx, item.def_id()))),
+        path: item.name(),
+        ty: item.ty().to_string(),
+        block_count,
+        statement_count,
+        crate_name: None,
+    }
+}
+
+// Enumerates the functions of every upstream crate (`--include-deps`),
+// reachable via `stable_mir::external_crates()`.
+fn external_fn_defs() -> Vec<(String, stable_mir::ty::FnDef)> {
+    stable_mir::external_crates()
+        .into_iter()
+        .flat_map(|krate| {
+            let crate_name = krate.name.clone();
+            krate
+                .fn_defs()
+                .into_iter()
+                .map(move |fn_def| (crate_name.clone(), fn_def))
+        })
+        .collect()
+}
+
+// Enumerates the trait declarations of every upstream crate (`--include-deps`).
+// StableMIR has no single query enumerating *all* external items the way
+// `all_local_items()` does for the local crate, so dependency coverage is
+// limited to what it exposes at crate granularity: functions and traits.
+// Structs/enums/consts/etc. from dependencies aren't reported.
+fn external_trait_decls() -> Vec<(String, stable_mir::ty::TraitDecl)> {
+    stable_mir::external_crates()
+        .into_iter()
+        .flat_map(|krate| {
+            let crate_name = krate.name.clone();
+            krate
+                .trait_decls()
+                .into_iter()
+                .map(move |trait_decl| (crate_name.clone(), trait_decl))
+        })
+        .collect()
+}
+
+// Builds the JSON record for an external function, resolving its body for
+// block/statement counts. `Instance::resolve` requires exactly as many
+// generic arguments as the function declares, and we have no concrete ones to
+// give it, so we only attempt resolution for non-generic functions; generic
+// functions are still reported, just without body stats, instead of risking
+// an arg-count mismatch.
+fn external_fn_record(tcx: TyCtxt, crate_name: String, fn_def: stable_mir::ty::FnDef) -> ItemRecord {
+    let def_id = rustc_internal::internal(tcx, fn_def.def_id());
+    let generic_param_count = tcx.generics_of(def_id).count();
+    let (block_count, statement_count) = if can_resolve_without_generic_args(generic_param_count) {
+        match Instance::resolve(fn_def, &stable_mir::ty::GenericArgs(vec![])) {
+            Ok(instance) => match instance.body() {
+                Some(body) => {
+                    let statement_count = body.blocks.iter().map(|bb| bb.statements.len()).sum();
+                    (Some(body.blocks.len()), Some(statement_count))
+                }
+                None => (None, None),
+            },
+            Err(_) => (None, None),
+        }
+    } else {
+        (None, None)
+    };
+    ItemRecord {
+        def_kind: "Fn".to_string(),
+        path: fn_def.name(),
+        ty: fn_def.ty().to_string(),
+        block_count,
+        statement_count,
+        crate_name: Some(crate_name),
+    }
+}
+
+// `Instance::resolve` requires exactly as many generic arguments as the
+// function declares, and we have none to give it, so resolution (and hence
+// the body/block stats it enables) is only attempted for functions with zero
+// generic parameters.
+fn can_resolve_without_generic_args(generic_param_count: usize) -> bool {
+    generic_param_count == 0
+}
+
+// Builds the JSON record for an external trait declaration. Traits have no
+// singular `Ty` or MIR body, unlike functions.
+fn external_trait_record(crate_name: String, trait_decl: stable_mir::ty::TraitDecl) -> ItemRecord {
+    ItemRecord {
+        def_kind: "Trait".to_string(),
+        path: trait_decl.name(),
+        ty: String::new(),
+        block_count: None,
+        statement_count: None,
+        crate_name: Some(crate_name),
+    }
+}
+
+// Resolves each `--find KIND:PATH` query against the crate's local items,
+// printing the matching item. Stops compilation as soon as a query has no
+// match, so this can be used as an assertion in test suites.
+fn run_find(tcx: TyCtxt, args: &StablePluginArgs) -> rustc_driver::Compilation {
+    let items = stable_mir::all_local_items();
+    for query in &args.find {
+        let (kind, path) = match parse_find_query(query) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                tcx.dcx().err(format!("invalid --find query \"{query}\": {err}"));
+                return rustc_driver::Compilation::Stop;
+            }
+        };
+        match get_item(tcx, &items, (kind, &path)) {
+            Some(item) => {
+                println!("There is an item \"{:?}\" of type \"{}\"", item, item.ty())
+            }
+            None => {
+                tcx.dcx().err(format!("no item found matching --find {query}"));
+                return rustc_driver::Compilation::Stop;
+            }
+        }
+    }
+    rustc_driver::Compilation::Continue
+}
+
+// Finds the item in `items` whose `DefKind` and path match `query`, mirroring
+// the `get_item` helper from the StableMIR crate-info test.
+fn get_item<'a>(
+    tcx: TyCtxt,
+    items: &'a [stable_mir::CrateItem],
+    query: (DefKind, &str),
+) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|item| {
+        let def_id = rustc_internal::internal(tcx, item.def_id());
+        tcx.def_kind(def_id) == query.0 && item.name() == query.1
+    })
+}
+
+// Parses a `KIND:PATH` query string, e.g. `Fn:foo::bar`.
+fn parse_find_query(raw: &str) -> Result<(DefKind, String), String> {
+    let (kind, path) = raw.split_once(':').ok_or("expected KIND:PATH")?;
+    let kind = parse_def_kind(kind).ok_or_else(|| format!("unknown DefKind \"{kind}\""))?;
+    Ok((kind, path.to_string()))
+}
+
+// Maps the handful of `DefKind` variants useful for `--find` from their name.
+fn parse_def_kind(raw: &str) -> Option<DefKind> {
+    Some(match raw {
+        "Fn" => DefKind::Fn,
+        "Struct" => DefKind::Struct,
+        "Enum" => DefKind::Enum,
+        "Union" => DefKind::Union,
+        "Trait" => DefKind::Trait,
+        "Const" => DefKind::Const,
+        "Mod" => DefKind::Mod,
+        "TyAlias" => DefKind::TyAlias,
+        _ => return None,
+    })
+}
+
+// Builds an inter-procedural call graph by walking MIR terminators outward
+// from the crate's entry point (or every local item if it has none),
+// recursing into callees' bodies even when they live in other crates, since
+// `-Zalways-encode-mir` keeps their MIR around.
+fn run_call_graph(tcx: TyCtxt, args: &StablePluginArgs) -> rustc_driver::Compilation {
+    let roots: Vec<stable_mir::CrateItem> = match stable_mir::entry_fn() {
+        Some(entry) => vec![entry],
+        None => stable_mir::all_local_items(),
+    };
+
+    let mut visited = HashSet::new();
+    let mut edges = Vec::new();
+    for root in roots {
+        if !root.has_body() {
+            continue;
+        }
+        let body = root.body();
+        let def_id = rustc_internal::internal(tcx, root.def_id());
+        walk_call_graph(
+            tcx,
+            def_id,
+            root.name(),
+            &body,
+            0,
+            args.call_graph_depth,
+            &mut visited,
+            &mut edges,
+        );
+    }
+
+    match args.format {
+        OutputFormat::Dot => println!("{}", edges_to_dot(&edges)),
+        OutputFormat::Json => {
+            let records: Vec<CallGraphEdge> = edges
+                .iter()
+                .map(|(caller, callee)| CallGraphEdge {
+                    caller: caller.clone(),
+                    callee: callee.clone(),
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&records).unwrap());
+        }
+        OutputFormat::Text => {
+            for (caller, callee) in &edges {
+                println!("{caller} -> {callee}");
+            }
+        }
+    }
+    rustc_driver::Compilation::Continue
+}
+
+// One edge's entry in the `--call-graph --format json` adjacency-list output.
+#[derive(Serialize)]
+struct CallGraphEdge {
+    caller: String,
+    callee: String,
+}
+
+// Recursively follows `Terminator::Call`s out of `body`, recording one edge
+// per call and stopping at `max_depth` or once a function has been visited.
+// Dedup/visited tracking keys on the real `DefId` rather than the printable
+// name: across crate boundaries it's common for unrelated functions to share
+// a display name (`new`, `clone`, `fmt`, ...), which would otherwise merge
+// distinct nodes and drop edges.
+fn walk_call_graph(
+    tcx: TyCtxt,
+    def_id: rustc_middle::ty::DefId,
+    name: String,
+    body: &Body,
+    depth: usize,
+    max_depth: usize,
+    visited: &mut HashSet<rustc_middle::ty::DefId>,
+    edges: &mut Vec<(String, String)>,
+) {
+    if depth > max_depth || !visited.insert(def_id) {
+        return;
+    }
+    for block in &body.blocks {
+        let TerminatorKind::Call { func, .. } = &block.terminator.kind else {
+            continue;
+        };
+        let Some(instance) = resolve_callee(func) else {
+            continue;
+        };
+        let callee_def_id = rustc_internal::internal(tcx, instance.def.def_id());
+        let callee_name = instance.name();
+        edges.push((name.clone(), callee_name.clone()));
+        if let Some(callee_body) = instance.body() {
+            walk_call_graph(
+                tcx,
+                callee_def_id,
+                callee_name,
+                &callee_body,
+                depth + 1,
+                max_depth,
+                visited,
+                edges,
+            );
+        }
+    }
+}
+
+// Resolves the `Instance` a call terminator's callee operand refers to, so
+// the traversal can recurse into its MIR regardless of which crate it's
+// defined in.
+fn resolve_callee(func: &Operand) -> Option<Instance> {
+    let Operand::Constant(constant) = func else {
+        return None;
+    };
+    let TyKind::RigidTy(RigidTy::FnDef(def, args)) = constant.const_.ty().kind() else {
+        return None;
+    };
+    Instance::resolve(def, &args).ok()
+}
+
+// Renders a call graph's edges as a Graphviz DOT digraph.
+fn edges_to_dot(edges: &[(String, String)]) -> String {
+    let mut dot = String::new();
+    let _ = writeln!(dot, "digraph call_graph {{");
+    for (caller, callee) in edges {
+        let _ = writeln!(
+            dot,
+            "  \"{}\" -> \"{}\";",
+            escape_dot_label(caller),
+            escape_dot_label(callee)
+        );
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+// Dumps the MIR of every local function item as a Graphviz DOT control-flow graph.
+// Non-function items (e.g. statics, consts) and functions without an available
+// body are skipped rather than panicking.
+fn dump_mir_cfgs(_tcx: TyCtxt, _args: &StablePluginArgs) -> rustc_driver::Compilation {
     for item in stable_mir::all_local_items() {
-        let msg = format!("There is an item \"{:?}\" of type \"{}\"", item, item.ty());
-        println!("{msg}");
+        if !matches!(item.kind(), stable_mir::ItemKind::Fn) || !item.has_body() {
+            continue;
+        }
+        let name = item.name();
+        let body = item.body();
+        println!("{}", body_to_dot(&name, &body));
     }
     rustc_driver::Compilation::Continue
 }
+
+// Renders a single function's MIR body as a Graphviz DOT digraph: one node per
+// basic block labeled with its statements, and edges derived from the block's
+// terminator.
+fn body_to_dot(name: &str, body: &Body) -> String {
+    let mut dot = String::new();
+    let _ = writeln!(dot, "digraph \"{}\" {{", name);
+    for (idx, block) in body.blocks.iter().enumerate() {
+        let mut label = format!("bb{idx}:\\l");
+        for statement in &block.statements {
+            let _ = write!(label, "{:?}\\l", statement);
+        }
+        let _ = writeln!(
+            dot,
+            "  bb{idx} [shape=box label=\"{}\"];",
+            escape_dot_label(&label)
+        );
+        for target in terminator_targets(&block.terminator.kind) {
+            let _ = writeln!(dot, "  bb{idx} -> bb{target};");
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+// Collects the basic block indices a terminator may transfer control to.
+fn terminator_targets(kind: &TerminatorKind) -> Vec<usize> {
+    match kind {
+        TerminatorKind::Goto { target } => vec![*target],
+        TerminatorKind::SwitchInt { targets, .. } => targets
+            .branches()
+            .map(|(_, target)| target)
+            .chain(std::iter::once(targets.otherwise()))
+            .collect(),
+        TerminatorKind::Call { target, unwind, .. } => {
+            target.iter().copied().chain(unwind_target(unwind)).collect()
+        }
+        TerminatorKind::Drop { target, unwind, .. } => {
+            std::iter::once(*target).chain(unwind_target(unwind)).collect()
+        }
+        TerminatorKind::Assert { target, unwind, .. } => {
+            std::iter::once(*target).chain(unwind_target(unwind)).collect()
+        }
+        TerminatorKind::InlineAsm { destination, unwind, .. } => {
+            destination.iter().copied().chain(unwind_target(unwind)).collect()
+        }
+        TerminatorKind::Return
+        | TerminatorKind::Unreachable
+        | TerminatorKind::Resume
+        | TerminatorKind::Abort => vec![],
+        _ => vec![],
+    }
+}
+
+// Extracts the cleanup block of an `UnwindAction`, if any.
+fn unwind_target(unwind: &UnwindAction) -> Option<usize> {
+    match unwind {
+        UnwindAction::Cleanup(target) => Some(*target),
+        UnwindAction::Continue | UnwindAction::Unreachable | UnwindAction::Terminate => None,
+    }
+}
+
+// Escapes the double quotes in a label so it can be embedded in a DOT string
+// literal. Backslashes are left as they are, so DOT escapes such as `\l` keep
+// their meaning.
+fn escape_dot_label(label: &str) -> String {
+    label.replace('"', "\\\"")
+}
+
+// These exercise the pieces of the plugin that don't require a live compiler
+// session: the DOT CFG emitter's per-block edge/label logic, the JSON record
+// schemas, `--find` query parsing, and the call-graph helpers.
+// `TerminatorKind` variants such as `Goto` and `Return` carry only plain data,
+// so they can be constructed directly in a unit test.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terminator_targets_goto_has_one_edge() {
+        assert_eq!(terminator_targets(&TerminatorKind::Goto { target: 3 }), vec![3]);
+    }
+
+    #[test]
+    fn terminator_targets_return_has_no_edges() {
+        assert!(terminator_targets(&TerminatorKind::Return).is_empty());
+        assert!(terminator_targets(&TerminatorKind::Unreachable).is_empty());
+    }
+
+    #[test]
+    fn unwind_target_only_cleanup_has_a_block() {
+        assert_eq!(unwind_target(&UnwindAction::Cleanup(5)), Some(5));
+        assert_eq!(unwind_target(&UnwindAction::Continue), None);
+        assert_eq!(unwind_target(&UnwindAction::Unreachable), None);
+        assert_eq!(unwind_target(&UnwindAction::Terminate), None);
+    }
+
+    #[test]
+    fn escape_dot_label_escapes_quotes() {
+        assert_eq!(escape_dot_label(r#"bb0: _1 = "x""#), r#"bb0: _1 = \"x\""#);
+    }
+
+    // Locks down the `--format json` item-listing schema: field names and
+    // the `None`/`Some` split between local and `--include-deps` items.
+    #[test]
+    fn item_record_json_schema() {
+        let local = ItemRecord {
+            def_kind: "Fn".to_string(),
+            path: "foo::bar".to_string(),
+            ty: "fn()".to_string(),
+            block_count: Some(2),
+            statement_count: Some(5),
+            crate_name: None,
+        };
+        let value = serde_json::to_value(&local).unwrap();
+        assert_eq!(value["def_kind"], "Fn");
+        assert_eq!(value["path"], "foo::bar");
+        assert_eq!(value["ty"], "fn()");
+        assert_eq!(value["block_count"], 2);
+        assert_eq!(value["statement_count"], 5);
+        assert!(value["crate_name"].is_null());
+
+        let dep = ItemRecord {
+            def_kind: "Fn".to_string(),
+            path: "std::mem::swap".to_string(),
+            ty: "fn()".to_string(),
+            block_count: None,
+            statement_count: None,
+            crate_name: Some("core".to_string()),
+        };
+        let value = serde_json::to_value(&dep).unwrap();
+        assert!(value["block_count"].is_null());
+        assert_eq!(value["crate_name"], "core");
+    }
+
+    // `--find`'s whole pitch is "an assertion tool in test suites", so its own
+    // parsing (the part testable without a live compiler session — matching
+    // against real items is exercised by `get_item`, which needs a `TyCtxt`)
+    // gets covered here: the happy path and both ways a query can be invalid.
+    #[test]
+    fn parse_find_query_accepts_kind_colon_path() {
+        let (kind, path) = parse_find_query("Fn:foo::bar").unwrap();
+        assert_eq!(kind, DefKind::Fn);
+        assert_eq!(path, "foo::bar");
+    }
+
+    #[test]
+    fn parse_find_query_rejects_missing_colon() {
+        assert!(parse_find_query("Fnfoobar").is_err());
+    }
+
+    #[test]
+    fn parse_find_query_rejects_unknown_kind() {
+        assert!(parse_find_query("Widget:foo").is_err());
+    }
+
+    #[test]
+    fn parse_def_kind_known_and_unknown() {
+        assert_eq!(parse_def_kind("Struct"), Some(DefKind::Struct));
+        assert_eq!(parse_def_kind("NotAKind"), None);
+    }
+
+    // `walk_call_graph` used to dedup visited nodes by
+    // `Instance::name()`/`CrateItem::name()`, so two distinct functions that
+    // merely render the same display name (e.g. `clone` from two different
+    // crates) were silently merged and real edges got dropped. It now keys its
+    // visited set on `DefId`; this checks that a set of that key type keeps two
+    // distinct ids apart and rejects a repeat. `walk_call_graph` itself needs a
+    // live compiler session and is not called here.
+    #[test]
+    fn call_graph_dedup_uses_def_id_not_display_name() {
+        use rustc_span::def_id::{DefIndex, LOCAL_CRATE};
+
+        let clone_for_foo = rustc_middle::ty::DefId {
+            krate: LOCAL_CRATE,
+            index: DefIndex::from_u32(0),
+        };
+        let clone_for_bar = rustc_middle::ty::DefId {
+            krate: LOCAL_CRATE,
+            index: DefIndex::from_u32(1),
+        };
+
+        let mut visited: HashSet<rustc_middle::ty::DefId> = HashSet::new();
+        assert!(visited.insert(clone_for_foo));
+        assert!(visited.insert(clone_for_bar));
+        assert!(!visited.insert(clone_for_foo));
+    }
+
+    // Locks down the `--call-graph --format json` edge schema.
+    #[test]
+    fn call_graph_edge_json_schema() {
+        let edge = CallGraphEdge {
+            caller: "foo".to_string(),
+            callee: "bar".to_string(),
+        };
+        let value = serde_json::to_value(&edge).unwrap();
+        assert_eq!(value["caller"], "foo");
+        assert_eq!(value["callee"], "bar");
+    }
+
+    // `external_fn_record` used to call `Instance::resolve` with zero generic
+    // arguments unconditionally, which mismatches the arity `Instance::resolve`
+    // expects for any generic function. This locks down the guard that skips
+    // resolution (and so, body stats) for anything but a non-generic function.
+    #[test]
+    fn can_resolve_without_generic_args_only_for_zero_params() {
+        assert!(can_resolve_without_generic_args(0));
+        assert!(!can_resolve_without_generic_args(1));
+        assert!(!can_resolve_without_generic_args(2));
+    }
+}